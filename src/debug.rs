@@ -0,0 +1,228 @@
+//! A small stepping debugger built on `Interpreter::step`.
+//!
+//! The REPL drives the interpreter one op at a time, printing tape and cursor
+//! state between steps and honoring breakpoints on a source offset or a cell
+//! value. A non-interactive tracer emits the same state as line-delimited JSON
+//! for external tools.
+
+use std::io::{self, BufRead, Write};
+
+use crate::{Interpreter, Op, Program};
+
+/// Run the interactive debugging REPL. Commands are read from `commands` (a
+/// terminal, typically) and the UI is written to `ui` (stderr); the program's
+/// own output still flows through the interpreter's writer.
+pub fn repl<I, O, R>(
+    interp: &mut Interpreter<I, O>,
+    code: &[u8],
+    mut commands: R,
+    ui: &mut impl Write,
+) -> io::Result<()>
+where
+    I: io::Read,
+    O: Write,
+    R: BufRead,
+{
+    let mut program = Program::compile(code, interp.cell_width())?;
+
+    let mut offset_breaks: Vec<usize> = Vec::new();
+    let mut cell_breaks: Vec<u32> = Vec::new();
+
+    print_state(interp, &program, ui)?;
+
+    let mut line = String::new();
+    loop {
+        write!(ui, "(bruck) ")?;
+        ui.flush()?;
+
+        line.clear();
+        if commands.read_line(&mut line)? == 0 {
+            break; // EOF on the command stream.
+        }
+
+        let mut parts = line.split_whitespace();
+        let command = match parts.next() {
+            Some(command) => command,
+            None => continue,
+        };
+        let arg = parts.next();
+
+        match command {
+            "s" | "step" => {
+                let count = arg.and_then(|a| a.parse::<usize>().ok()).unwrap_or(1);
+                for _ in 0..count {
+                    match interp.step(&mut program)? {
+                        Some(outcome) if outcome.halted => {
+                            print_state(interp, &program, ui)?;
+                            writeln!(ui, "program halted")?;
+                            break;
+                        }
+                        Some(_) => {}
+                        None => {
+                            writeln!(ui, "program halted")?;
+                            break;
+                        }
+                    }
+                }
+                print_state(interp, &program, ui)?;
+            }
+            "c" | "continue" => {
+                loop {
+                    match interp.step(&mut program)? {
+                        None => {
+                            writeln!(ui, "program halted")?;
+                            break;
+                        }
+                        Some(outcome) if outcome.halted => {
+                            writeln!(ui, "program halted")?;
+                            break;
+                        }
+                        Some(_) => {
+                            if at_breakpoint(interp, &program, &offset_breaks, &cell_breaks) {
+                                writeln!(ui, "breakpoint hit")?;
+                                break;
+                            }
+                        }
+                    }
+                }
+                print_state(interp, &program, ui)?;
+            }
+            "p" | "print" => {
+                let radius = arg.and_then(|a| a.parse::<usize>().ok()).unwrap_or(10);
+                print_window(interp, radius, ui)?;
+            }
+            "b" | "break" => match arg.and_then(|a| a.parse::<usize>().ok()) {
+                Some(offset) => {
+                    offset_breaks.push(offset);
+                    writeln!(ui, "breakpoint at source offset {offset}")?;
+                }
+                None => writeln!(ui, "usage: break <source-offset>")?,
+            },
+            "w" | "watch" => match arg.and_then(|a| a.parse::<u32>().ok()) {
+                Some(value) => {
+                    cell_breaks.push(value);
+                    writeln!(ui, "breakpoint when head cell == {value}")?;
+                }
+                None => writeln!(ui, "usage: watch <cell-value>")?,
+            },
+            "q" | "quit" => break,
+            "h" | "help" => print_help(ui)?,
+            other => writeln!(ui, "unknown command: {other} (try `help`)")?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the program to completion, emitting one JSON object per executed step.
+pub fn trace<I, O>(
+    interp: &mut Interpreter<I, O>,
+    code: &[u8],
+    out: &mut impl Write,
+) -> io::Result<()>
+where
+    I: io::Read,
+    O: Write,
+{
+    let mut program = Program::compile(code, interp.cell_width())?;
+    while let Some(outcome) = interp.step(&mut program)? {
+        writeln!(
+            out,
+            "{{\"ip\":{},\"head\":{},\"op\":\"{:?}\",\"cell\":{}}}",
+            program.ip,
+            interp.head(),
+            outcome.op,
+            interp.current_cell(),
+        )?;
+    }
+    Ok(())
+}
+
+fn at_breakpoint<I, O>(
+    interp: &Interpreter<I, O>,
+    program: &Program,
+    offset_breaks: &[usize],
+    cell_breaks: &[u32],
+) -> bool
+where
+    I: io::Read,
+    O: Write,
+{
+    if cell_breaks.contains(&interp.current_cell()) {
+        return true;
+    }
+    program
+        .span_at_ip()
+        .is_some_and(|offset| offset_breaks.contains(&offset))
+}
+
+fn print_state<I, O>(
+    interp: &Interpreter<I, O>,
+    program: &Program,
+    ui: &mut impl Write,
+) -> io::Result<()>
+where
+    I: io::Read,
+    O: Write,
+{
+    match program.op_at_ip() {
+        Some(op) => writeln!(
+            ui,
+            "ip={} head={} cell={} next={:?}",
+            program.ip(),
+            interp.head(),
+            interp.current_cell(),
+            op,
+        ),
+        None => writeln!(
+            ui,
+            "ip={} head={} cell={} next=<end>",
+            program.ip(),
+            interp.head(),
+            interp.current_cell(),
+        ),
+    }
+}
+
+fn print_window<I, O>(
+    interp: &Interpreter<I, O>,
+    radius: usize,
+    ui: &mut impl Write,
+) -> io::Result<()>
+where
+    I: io::Read,
+    O: Write,
+{
+    for (offset, value) in interp.window(radius) {
+        let marker = if offset == 0 { "->" } else { "  " };
+        writeln!(ui, "{marker} [{offset:+}] {value}")?;
+    }
+    Ok(())
+}
+
+fn print_help(ui: &mut impl Write) -> io::Result<()> {
+    writeln!(ui, "commands:")?;
+    writeln!(ui, "  s, step [n]   execute n ops (default 1)")?;
+    writeln!(ui, "  c, continue   run to the next breakpoint or halt")?;
+    writeln!(ui, "  p, print [r]  show cells within r of the head (default 10)")?;
+    writeln!(ui, "  b, break <o>  break when the op at source offset o is next")?;
+    writeln!(ui, "  w, watch <v>  break when the head cell equals v")?;
+    writeln!(ui, "  q, quit       leave the debugger")?;
+    Ok(())
+}
+
+/// Accessors used by the debugger's state display. They are private to this
+/// module, which is the only place that uses them.
+impl Program {
+    fn ip(&self) -> usize {
+        self.ip
+    }
+
+    fn op_at_ip(&self) -> Option<Op> {
+        self.ops.get(self.ip).copied()
+    }
+
+    fn span_at_ip(&self) -> Option<usize> {
+        self.spans.get(self.ip).copied()
+    }
+}
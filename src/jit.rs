@@ -0,0 +1,298 @@
+//! Optional native backend: compile the fused IR to machine code with
+//! Cranelift instead of walking the `Vec<Op>` in the interpreter loop.
+//!
+//! The whole module is gated behind the `jit` feature so the default build
+//! keeps its lean dependency tree. When the feature is off, `main` falls back
+//! to the interpreter, so `Interpreter`'s public API is unaffected.
+
+use std::io::{self, Read, Write};
+use std::slice;
+
+use cranelift_codegen::ir::{types, AbiParam, InstBuilder, MemFlags, TrapCode, Value};
+use cranelift_codegen::ir::condcodes::IntCC;
+use cranelift_codegen::settings::{self, Configurable};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{Linkage, Module};
+
+use crate::Op;
+
+/// Runtime state handed to the compiled function via an opaque pointer. The
+/// shim functions below cast it back and drive the real I/O and tape-growth
+/// plumbing.
+struct Runtime<'a> {
+    input: &'a mut dyn Read,
+    output: &'a mut dyn Write,
+    cells: Vec<u8>,
+    /// First error observed inside a shim, surfaced once the function returns.
+    error: Option<io::Error>,
+}
+
+/// Shim invoked for `.`: write the byte at `head` to the output stream.
+extern "C" fn shim_output(rt: *mut Runtime, value: u8) {
+    let rt = unsafe { &mut *rt };
+    if rt.error.is_none() {
+        if let Err(e) = rt.output.write_all(slice::from_ref(&value)) {
+            rt.error = Some(e);
+        }
+    }
+}
+
+/// Shim invoked for `,`: read one byte, mapping EOF to zero (the interpreter's
+/// default policy). Returns the byte to store in the head cell.
+extern "C" fn shim_input(rt: *mut Runtime) -> u8 {
+    let rt = unsafe { &mut *rt };
+    if rt.error.is_some() {
+        return 0;
+    }
+    let mut byte = 0u8;
+    match rt.input.read_exact(slice::from_mut(&mut byte)) {
+        Ok(()) => byte,
+        Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => 0,
+        Err(e) => {
+            rt.error = Some(e);
+            0
+        }
+    }
+}
+
+/// Shim invoked after every rightward `Move` and before every `MulAdd` with a
+/// positive offset: if cell `needed` is past the allocated tape, grow the
+/// backing buffer with zero cells. Returns the (possibly moved) base pointer
+/// so the compiled code can refresh its cached base.
+extern "C" fn shim_grow(rt: *mut Runtime, needed: usize) -> *mut u8 {
+    let rt = unsafe { &mut *rt };
+    if needed >= rt.cells.len() {
+        rt.cells.resize(needed + 1, 0);
+    }
+    rt.cells.as_mut_ptr()
+}
+
+/// Compile `ops` to native code and run it against `input`/`output`.
+pub fn run(ops: &[Op], input: &mut dyn Read, output: &mut dyn Write) -> io::Result<()> {
+    let mut flag_builder = settings::builder();
+    flag_builder
+        .set("use_colocated_libcalls", "false")
+        .map_err(jit_err)?;
+    flag_builder.set("is_pic", "false").map_err(jit_err)?;
+    let isa_builder = cranelift_native::builder().map_err(jit_err)?;
+    let isa = isa_builder
+        .finish(settings::Flags::new(flag_builder))
+        .map_err(jit_err)?;
+
+    let mut builder = JITBuilder::with_isa(isa, cranelift_module::default_libcall_names());
+    builder.symbol("shim_output", shim_output as *const u8);
+    builder.symbol("shim_input", shim_input as *const u8);
+    builder.symbol("shim_grow", shim_grow as *const u8);
+    let mut module = JITModule::new(builder);
+
+    let ptr = module.target_config().pointer_type();
+    let func_id = compile(&mut module, ops, ptr)?;
+    module.finalize_definitions().map_err(jit_err)?;
+
+    // Start with a megabyte of tape; `shim_grow` extends it on demand.
+    let mut runtime = Runtime {
+        input,
+        output,
+        cells: vec![0u8; 1 << 20],
+        error: None,
+    };
+
+    let code = module.get_finalized_function(func_id);
+    let entry =
+        unsafe { std::mem::transmute::<_, extern "C" fn(*mut Runtime, *mut u8)>(code) };
+    let base = runtime.cells.as_mut_ptr();
+    entry(&mut runtime as *mut Runtime, base);
+
+    match runtime.error.take() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Emit a single `fn(*mut Runtime, *mut u8)` that threads the tape base and a
+/// running head offset through the `ops`.
+fn compile(
+    module: &mut JITModule,
+    ops: &[Op],
+    ptr: types::Type,
+) -> io::Result<cranelift_module::FuncId> {
+    // Declare the shim signatures so we can reference them as callees.
+    let mut out_sig = module.make_signature();
+    out_sig.params.push(AbiParam::new(ptr));
+    out_sig.params.push(AbiParam::new(types::I8));
+    let out_id = module
+        .declare_function("shim_output", Linkage::Import, &out_sig)
+        .map_err(jit_err)?;
+
+    let mut in_sig = module.make_signature();
+    in_sig.params.push(AbiParam::new(ptr));
+    in_sig.returns.push(AbiParam::new(types::I8));
+    let in_id = module
+        .declare_function("shim_input", Linkage::Import, &in_sig)
+        .map_err(jit_err)?;
+
+    let mut grow_sig = module.make_signature();
+    grow_sig.params.push(AbiParam::new(ptr));
+    grow_sig.params.push(AbiParam::new(ptr));
+    grow_sig.returns.push(AbiParam::new(ptr));
+    let grow_id = module
+        .declare_function("shim_grow", Linkage::Import, &grow_sig)
+        .map_err(jit_err)?;
+
+    let mut sig = module.make_signature();
+    sig.params.push(AbiParam::new(ptr)); // *mut Runtime
+    sig.params.push(AbiParam::new(ptr)); // *mut u8 tape base
+    let func_id = module
+        .declare_function("bf_main", Linkage::Export, &sig)
+        .map_err(jit_err)?;
+
+    let mut ctx = module.make_context();
+    ctx.func.signature = sig;
+    let mut fb_ctx = FunctionBuilderContext::new();
+    let mut bcx = FunctionBuilder::new(&mut ctx.func, &mut fb_ctx);
+
+    let out_ref = module.declare_func_in_func(out_id, bcx.func);
+    let in_ref = module.declare_func_in_func(in_id, bcx.func);
+    let grow_ref = module.declare_func_in_func(grow_id, bcx.func);
+
+    let entry = bcx.create_block();
+    bcx.append_block_params_for_function_params(entry);
+    bcx.switch_to_block(entry);
+    let rt = bcx.block_params(entry)[0];
+
+    // `base` and `head` are kept in variables so loop blocks can reload them.
+    use cranelift_frontend::Variable;
+    let base = Variable::new(0);
+    let head = Variable::new(1);
+    bcx.declare_var(base, ptr);
+    bcx.declare_var(head, ptr);
+    let base0 = bcx.block_params(entry)[1];
+    let zero = bcx.ins().iconst(ptr, 0);
+    bcx.def_var(base, base0);
+    bcx.def_var(head, zero);
+
+    // Each loop gets a header, body and exit block; open loops are matched
+    // via a stack of their (header, exit) pairs.
+    let mut loop_blocks: Vec<(cranelift_codegen::ir::Block, cranelift_codegen::ir::Block)> =
+        Vec::new();
+
+    let flags = MemFlags::new();
+    for op in ops {
+        match *op {
+            Op::Add(delta) => {
+                let addr = cur_addr(&mut bcx, base, head);
+                let cur = bcx.ins().load(types::I8, flags, addr, 0);
+                let next = bcx.ins().iadd_imm(cur, delta as i64);
+                bcx.ins().store(flags, next, addr, 0);
+            }
+            Op::Move(n) => {
+                let h = bcx.use_var(head);
+                let moved = bcx.ins().iadd_imm(h, n as i64);
+                bcx.def_var(head, moved);
+                // Only rightward moves can outrun the allocation; a leftward
+                // move never does, and passing its (possibly underflowed)
+                // offset to `shim_grow` would request a gigantic allocation.
+                if n > 0 {
+                    let idx = bcx.use_var(head);
+                    ensure_index(&mut bcx, rt, grow_ref, base, idx);
+                } else if n < 0 {
+                    // `run_jit` only admits `EdgeBehavior::Panic`, so a move
+                    // below the origin must trap rather than form an
+                    // out-of-bounds pointer from a negative offset.
+                    let idx = bcx.use_var(head);
+                    let zero = bcx.ins().iconst(ptr, 0);
+                    let underflow = bcx.ins().icmp(IntCC::SignedLessThan, idx, zero);
+                    bcx.ins().trapnz(underflow, TrapCode::HeapOutOfBounds);
+                }
+            }
+            Op::Output(count) => {
+                for _ in 0..count {
+                    let addr = cur_addr(&mut bcx, base, head);
+                    let val = bcx.ins().load(types::I8, flags, addr, 0);
+                    bcx.ins().call(out_ref, &[rt, val]);
+                }
+            }
+            Op::Input => {
+                let call = bcx.ins().call(in_ref, &[rt]);
+                let val = bcx.inst_results(call)[0];
+                let addr = cur_addr(&mut bcx, base, head);
+                bcx.ins().store(flags, val, addr, 0);
+            }
+            Op::SetZero => {
+                let addr = cur_addr(&mut bcx, base, head);
+                let z = bcx.ins().iconst(types::I8, 0);
+                bcx.ins().store(flags, z, addr, 0);
+            }
+            Op::MulAdd { offset, factor } => {
+                // Grow first if the target cell is past the allocation, so the
+                // load/store below can't run off the end like `Tape::mul_add`
+                // avoids by growing.
+                if offset > 0 {
+                    let h = bcx.use_var(head);
+                    let idx = bcx.ins().iadd_imm(h, offset as i64);
+                    ensure_index(&mut bcx, rt, grow_ref, base, idx);
+                }
+                let addr = cur_addr(&mut bcx, base, head);
+                let head_val = bcx.ins().load(types::I8, flags, addr, 0);
+                let off = bcx.ins().iconst(ptr, offset as i64);
+                let target = bcx.ins().iadd(addr, off);
+                let tgt_val = bcx.ins().load(types::I8, flags, target, 0);
+                let f = bcx.ins().iconst(types::I8, factor as i64);
+                let prod = bcx.ins().imul(head_val, f);
+                let sum = bcx.ins().iadd(tgt_val, prod);
+                bcx.ins().store(flags, sum, target, 0);
+            }
+            Op::JumpIfZero(_) => {
+                let header = bcx.create_block();
+                let body = bcx.create_block();
+                let exit = bcx.create_block();
+                bcx.ins().jump(header, &[]);
+                bcx.switch_to_block(header);
+                let addr = cur_addr(&mut bcx, base, head);
+                let cur = bcx.ins().load(types::I8, flags, addr, 0);
+                bcx.ins().brif(cur, body, &[], exit, &[]);
+                bcx.switch_to_block(body);
+                loop_blocks.push((header, exit));
+            }
+            Op::JumpIfNonZero(_) => {
+                let (header, exit) = loop_blocks.pop().expect("balanced loops");
+                bcx.ins().jump(header, &[]);
+                bcx.seal_block(header);
+                bcx.switch_to_block(exit);
+            }
+        }
+    }
+
+    bcx.ins().return_(&[]);
+    bcx.seal_all_blocks();
+    bcx.finalize();
+
+    module.define_function(func_id, &mut ctx).map_err(jit_err)?;
+    module.clear_context(&mut ctx);
+    Ok(func_id)
+}
+
+fn cur_addr(bcx: &mut FunctionBuilder, base: cranelift_frontend::Variable, head: cranelift_frontend::Variable) -> Value {
+    let b = bcx.use_var(base);
+    let h = bcx.use_var(head);
+    bcx.ins().iadd(b, h)
+}
+
+/// Ensure the tape covers cell `index`, refreshing the cached `base` pointer
+/// in case `shim_grow` reallocated. `index` must be non-negative.
+fn ensure_index(
+    bcx: &mut FunctionBuilder,
+    rt: Value,
+    grow_ref: cranelift_codegen::ir::FuncRef,
+    base: cranelift_frontend::Variable,
+    index: Value,
+) {
+    let call = bcx.ins().call(grow_ref, &[rt, index]);
+    let new_base = bcx.inst_results(call)[0];
+    bcx.def_var(base, new_base);
+}
+
+fn jit_err<E: std::fmt::Display>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, format!("jit backend unavailable: {e}"))
+}
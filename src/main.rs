@@ -1,70 +1,399 @@
 #![allow(dead_code)]
 
+use std::collections::VecDeque;
 use std::env;
 use std::fs::File;
 use std::io::{self, BufReader, BufWriter, Read, Write};
 use std::process;
 use std::slice;
 
+mod debug;
+
+#[cfg(feature = "jit")]
+mod jit;
+
+/// Cell width in bits. Cells are stored in the widest representation and masked
+/// down to this width on every write so arithmetic wraps at the right boundary.
+#[derive(Clone, Copy)]
+pub enum CellWidth {
+    Eight,
+    Sixteen,
+    ThirtyTwo,
+}
+
+impl CellWidth {
+    fn mask(self) -> u32 {
+        match self {
+            CellWidth::Eight => 0xFF,
+            CellWidth::Sixteen => 0xFFFF,
+            CellWidth::ThirtyTwo => u32::MAX,
+        }
+    }
+
+    /// The wrapping modulus for this width, wide enough to hold `2^32`.
+    fn modulus(self) -> i64 {
+        match self {
+            CellWidth::Eight => 1 << 8,
+            CellWidth::Sixteen => 1 << 16,
+            CellWidth::ThirtyTwo => 1 << 32,
+        }
+    }
+}
+
+/// What `left` does when the head is already at the origin.
+#[derive(Clone, Copy)]
+pub enum EdgeBehavior {
+    /// Panic — the historical behavior.
+    Panic,
+    /// Wrap around to the far (high) end of the tape.
+    Wrap,
+    /// Grow a fresh zero cell below the origin.
+    Grow,
+}
+
+/// What `,` stores into the current cell at end of input.
+#[derive(Clone, Copy)]
+pub enum EofPolicy {
+    /// Set the cell to zero — the historical default.
+    Zero,
+    /// Set every bit of the cell.
+    AllOnes,
+    /// Leave the cell unchanged.
+    Unchanged,
+}
+
+/// Runtime dialect knobs. The default is 8-bit wrapping cells, a doubly-
+/// unbounded tape (`left` at the origin grows a fresh zero rather than
+/// panicking), and `,` at EOF zeroing the cell. `--edge panic` restores the
+/// original bounded behavior.
+#[derive(Clone, Copy)]
+pub struct Config {
+    pub cell_width: CellWidth,
+    pub edge: EdgeBehavior,
+    pub eof: EofPolicy,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            cell_width: CellWidth::Eight,
+            edge: EdgeBehavior::Grow,
+            eof: EofPolicy::Zero,
+        }
+    }
+}
+
+/// The tape grows in both directions: `right` pushes zero cells onto the back
+/// and `left` (under `EdgeBehavior::Grow`) prepends them onto the front. A
+/// `VecDeque` keeps both ends amortized `O(1)`, which the hot loop relies on.
 struct Tape {
     head: usize,
-    cells: Vec<u8>,
+    cells: VecDeque<u32>,
+    config: Config,
 }
 
 impl Tape {
-    pub fn new() -> Self {
-        let mut cells = Vec::with_capacity(1024);
-        cells.push(0);
+    pub fn new(config: Config) -> Self {
+        let mut cells = VecDeque::with_capacity(1024);
+        cells.push_back(0);
         Tape {
             head: 0,
             cells,
+            config,
         }
     }
 
     pub fn right(&mut self) {
         if self.head == self.cells.len() - 1 {
-            self.cells.push(0);
+            self.cells.push_back(0);
         }
         self.head += 1;
     }
 
     pub fn left(&mut self) {
         if self.head == 0 {
-            panic!("Tried to go to a negative tape index!");
+            match self.config.edge {
+                EdgeBehavior::Panic => panic!("Tried to go to a negative tape index!"),
+                EdgeBehavior::Wrap => self.head = self.cells.len() - 1,
+                // Prepend a fresh zero; the head stays at the new front cell.
+                EdgeBehavior::Grow => self.cells.push_front(0),
+            }
         } else {
             self.head -= 1;
         }
     }
 
     pub fn increment(&mut self) {
-        self.cells[self.head] = self.cells[self.head].wrapping_add(1);
+        self.store(self.head, self.cells[self.head].wrapping_add(1));
     }
 
     pub fn decrement(&mut self) {
-        self.cells[self.head] = self.cells[self.head].wrapping_sub(1);
+        self.store(self.head, self.cells[self.head].wrapping_sub(1));
+    }
+
+    pub fn add(&mut self, delta: u32) {
+        let value = self.cells[self.head].wrapping_add(delta);
+        self.store(self.head, value);
+    }
+
+    pub fn move_by(&mut self, n: isize) {
+        if n >= 0 {
+            for _ in 0..n {
+                self.right();
+            }
+        } else {
+            for _ in 0..-n {
+                self.left();
+            }
+        }
+    }
+
+    pub fn set_zero(&mut self) {
+        self.cells[self.head] = 0;
+    }
+
+    pub fn mul_add(&mut self, offset: isize, factor: u32) {
+        let mut target = self.head as isize + offset;
+        if target < 0 {
+            // Grow the front so the negative offset lands on a real cell; the
+            // head (and every other index) shifts up by the amount prepended.
+            let need = (-target) as usize;
+            for _ in 0..need {
+                self.cells.push_front(0);
+            }
+            self.head += need;
+            target = 0;
+        }
+        let target = target as usize;
+        while target >= self.cells.len() {
+            self.cells.push_back(0);
+        }
+        let value = self.cells[self.head];
+        let sum = self.cells[target].wrapping_add(value.wrapping_mul(factor));
+        self.store(target, sum);
     }
 
     pub fn output(&self, writer: &mut impl Write) -> io::Result<()> {
-        writer.write_all(slice::from_ref(&self.cells[self.head]))
+        // Output is byte-oriented regardless of cell width; emit the low byte.
+        let byte = (self.cells[self.head] & 0xFF) as u8;
+        writer.write_all(slice::from_ref(&byte))
     }
 
     pub fn input(&mut self, reader: &mut impl Read) -> io::Result<()> {
-        if let Err(e) = reader.read_exact(slice::from_mut(&mut self.cells[self.head])) {
-            match e.kind() {
-                io::ErrorKind::UnexpectedEof => {
-                    self.cells[self.head] = 0;
-                    Ok(())
+        let mut byte = 0u8;
+        match reader.read_exact(slice::from_mut(&mut byte)) {
+            Ok(()) => {
+                self.store(self.head, byte as u32);
+                Ok(())
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                match self.config.eof {
+                    EofPolicy::Zero => self.cells[self.head] = 0,
+                    EofPolicy::AllOnes => self.cells[self.head] = self.config.cell_width.mask(),
+                    EofPolicy::Unchanged => {}
                 }
-                _ => Err(e),
+                Ok(())
             }
-        } else {
-            Ok(())
+            Err(e) => Err(e),
         }
     }
 
     pub fn is_zero(&self) -> bool {
         self.cells[self.head] == 0
     }
+
+    /// Write `value` masked to the configured cell width.
+    fn store(&mut self, index: usize, value: u32) {
+        self.cells[index] = value & self.config.cell_width.mask();
+    }
+}
+
+/// A single fused instruction of the compiled program.
+///
+/// The source is lowered to a `Vec<Op>` once up front so the hot loop can
+/// dispatch over wide operations (a whole run of `+` as one `Add`) instead of
+/// re-reading the program a character at a time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Add(u32),
+    Move(isize),
+    Output(u32),
+    Input,
+    JumpIfZero(usize),
+    JumpIfNonZero(usize),
+    SetZero,
+    MulAdd { offset: isize, factor: u32 },
+}
+
+/// Try to interpret a loop body (the bytes between `[` and `]`) as a balanced
+/// copy/multiply idiom: only `+`/`-`/`<`/`>`, a net pointer move of zero, and a
+/// net decrement of one on the head cell. A body that touches only the head
+/// cell with a net change of plus or minus one (`[-]`, `[+]`) is a clear loop.
+/// On success the loop is equivalent to a sequence of `MulAdd`s for each
+/// touched offset followed by `SetZero`.
+fn try_simple_loop(body: &[u8], width: CellWidth) -> Option<Vec<Op>> {
+    let mut offset: isize = 0;
+    let mut deltas: Vec<(isize, i32)> = Vec::new();
+
+    for &character in body {
+        match character as char {
+            '+' => record(&mut deltas, offset, 1),
+            '-' => record(&mut deltas, offset, -1),
+            '>' => offset += 1,
+            '<' => offset -= 1,
+            '[' | ']' | '.' | ',' => return None,
+            _ => {}
+        }
+    }
+
+    if offset != 0 {
+        return None;
+    }
+
+    let head_delta = deltas.iter().find(|&&(o, _)| o == 0).map(|&(_, d)| d);
+
+    // `[-]`/`[+]` touch only the head cell and merely clear it.
+    if deltas.len() == 1 && matches!(head_delta, Some(1) | Some(-1)) {
+        return Some(vec![Op::SetZero]);
+    }
+
+    // For a multiply loop the head cell must be decremented by exactly one per
+    // iteration, so the other cells' deltas become the multiplication factors.
+    if head_delta != Some(-1) {
+        return None;
+    }
+
+    let modulus = width.modulus();
+    let mut ops = Vec::new();
+    for &(o, d) in &deltas {
+        let factor = (d as i64).rem_euclid(modulus) as u32;
+        if o != 0 && factor != 0 {
+            ops.push(Op::MulAdd { offset: o, factor });
+        }
+    }
+    ops.push(Op::SetZero);
+    Some(ops)
+}
+
+fn record(deltas: &mut Vec<(isize, i32)>, offset: isize, by: i32) {
+    if let Some(entry) = deltas.iter_mut().find(|(o, _)| *o == offset) {
+        entry.1 += by;
+    } else {
+        deltas.push((offset, by));
+    }
+}
+
+/// A compiled program plus a cursor into it. Execution state is kept here
+/// rather than inlined in `run` so the interpreter can be driven one step at a
+/// time (e.g. by the debugger).
+#[derive(Debug)]
+struct Program {
+    ops: Vec<Op>,
+    /// `spans[k]` is the source offset the k-th op was lowered from — used to
+    /// map breakpoints expressed in source coordinates back onto ops.
+    spans: Vec<usize>,
+    ip: usize,
+}
+
+impl Program {
+    /// Lower raw Brainfuck source to a fused program, recognizing the common
+    /// `[-]`/multiply-loop idioms along the way. Unbalanced brackets are
+    /// reported as `InvalidData` before any execution happens.
+    pub fn compile(code: &[u8], width: CellWidth) -> io::Result<Self> {
+        let jumps = build_jumps(code)?;
+        let modulus = width.modulus();
+
+        let mut ops: Vec<Op> = Vec::new();
+        let mut spans: Vec<usize> = Vec::new();
+        let mut loop_stack: Vec<usize> = Vec::new();
+
+        let mut i = 0;
+        while i < code.len() {
+            let start = i;
+            match code[i] as char {
+                '+' | '-' => {
+                    let mut delta: i64 = 0;
+                    while i < code.len() && (code[i] == b'+' || code[i] == b'-') {
+                        delta += if code[i] == b'+' { 1 } else { -1 };
+                        i += 1;
+                    }
+                    let delta = delta.rem_euclid(modulus) as u32;
+                    if delta != 0 {
+                        ops.push(Op::Add(delta));
+                        spans.push(start);
+                    }
+                }
+                '>' | '<' => {
+                    let mut net: isize = 0;
+                    while i < code.len() && (code[i] == b'>' || code[i] == b'<') {
+                        net += if code[i] == b'>' { 1 } else { -1 };
+                        i += 1;
+                    }
+                    if net != 0 {
+                        ops.push(Op::Move(net));
+                        spans.push(start);
+                    }
+                }
+                '.' => {
+                    let mut count: u32 = 0;
+                    while i < code.len() && code[i] == b'.' {
+                        count += 1;
+                        i += 1;
+                    }
+                    ops.push(Op::Output(count));
+                    spans.push(start);
+                }
+                ',' => {
+                    ops.push(Op::Input);
+                    spans.push(start);
+                    i += 1;
+                }
+                '[' => {
+                    let close = jumps[i];
+                    if let Some(simple) = try_simple_loop(&code[(i + 1)..close], width) {
+                        for op in simple {
+                            ops.push(op);
+                            spans.push(start);
+                        }
+                        i = close + 1;
+                    } else {
+                        loop_stack.push(ops.len());
+                        ops.push(Op::JumpIfZero(0));
+                        spans.push(start);
+                        i += 1;
+                    }
+                }
+                ']' => {
+                    // `build_jumps` already validated balance, so this pops.
+                    let open = loop_stack.pop().expect("balanced brackets");
+                    let here = ops.len();
+                    ops.push(Op::JumpIfNonZero(open));
+                    spans.push(start);
+                    if let Op::JumpIfZero(target) = &mut ops[open] {
+                        *target = here;
+                    }
+                    i += 1;
+                }
+                _ => {
+                    i += 1;
+                }
+            }
+        }
+
+        Ok(Program {
+            ops,
+            spans,
+            ip: 0,
+        })
+    }
+}
+
+/// Outcome of a single `step`: the op that ran and whether the program has now
+/// reached its end.
+#[derive(Clone, Copy, Debug)]
+struct StepOutcome {
+    op: Op,
+    halted: bool,
 }
 
 struct Interpreter<I, O> {
@@ -74,44 +403,104 @@ struct Interpreter<I, O> {
 }
 
 impl<I: Read, O: Write> Interpreter<I, O> {
-    pub fn new(input: I, output: O) -> Self {
+    pub fn new(input: I, output: O, config: Config) -> Self {
         Self {
             input,
             output,
-            tape: Tape::new(),
+            tape: Tape::new(config),
         }
     }
 
     pub fn run(&mut self, code: &[u8]) -> io::Result<()> {
-        let mut i: usize = 0;
-        loop {
-            if i == code.len() {
-                break;
-            }
+        let mut program = Program::compile(code, self.tape.config.cell_width)?;
+        while self.step(&mut program)?.is_some() {}
+        Ok(())
+    }
 
-            match code[i] as char {
-                '>' => self.tape.right(),
-                '<' => self.tape.left(),
-                '+' => self.tape.increment(),
-                '-' => self.tape.decrement(),
-                '.' => self.tape.output(&mut self.output)?,
-                ',' => self.tape.input(&mut self.input)?,
-                '[' => {
-                    if self.tape.is_zero() {
-                        i = self.matching_for_left_paren(i, code)?;
-                    }
+    /// The configured cell width, so callers that compile a `Program` directly
+    /// (the debugger) fold arithmetic at the same boundary the tape wraps at.
+    pub fn cell_width(&self) -> CellWidth {
+        self.tape.config.cell_width
+    }
+
+    /// Execute the op at `program.ip` and advance the cursor. Returns the op
+    /// that ran and whether the program has halted, or `None` if the cursor is
+    /// already past the end. `run` is a loop over this method, so both paths
+    /// execute the same code.
+    pub fn step(&mut self, program: &mut Program) -> io::Result<Option<StepOutcome>> {
+        if program.ip >= program.ops.len() {
+            return Ok(None);
+        }
+
+        let op = program.ops[program.ip];
+        match op {
+            Op::Add(delta) => self.tape.add(delta),
+            Op::Move(n) => self.tape.move_by(n),
+            Op::Output(count) => {
+                for _ in 0..count {
+                    self.tape.output(&mut self.output)?;
                 }
-                ']' => {
-                    if !self.tape.is_zero() {
-                        i = self.matching_for_right_paren(i, code)?;
-                    }
+            }
+            Op::Input => self.tape.input(&mut self.input)?,
+            Op::SetZero => self.tape.set_zero(),
+            Op::MulAdd { offset, factor } => self.tape.mul_add(offset, factor),
+            Op::JumpIfZero(target) => {
+                if self.tape.is_zero() {
+                    program.ip = target;
+                }
+            }
+            Op::JumpIfNonZero(target) => {
+                if !self.tape.is_zero() {
+                    program.ip = target;
                 }
-                _ => {}
             }
-            i += 1;
         }
+        program.ip += 1;
 
-        Ok(())
+        Ok(Some(StepOutcome {
+            op,
+            halted: program.ip >= program.ops.len(),
+        }))
+    }
+
+    /// Current head position, as an index into the live tape.
+    pub fn head(&self) -> usize {
+        self.tape.head
+    }
+
+    /// Value of the cell under the head.
+    pub fn current_cell(&self) -> u32 {
+        self.tape.cells[self.tape.head]
+    }
+
+    /// A window of cells centered on the head, returned as `(offset, value)`
+    /// pairs where `offset` is relative to the head.
+    pub fn window(&self, radius: usize) -> Vec<(isize, u32)> {
+        let lo = self.tape.head.saturating_sub(radius);
+        let hi = (self.tape.head + radius).min(self.tape.cells.len() - 1);
+        (lo..=hi)
+            .map(|i| (i as isize - self.tape.head as isize, self.tape.cells[i]))
+            .collect()
+    }
+
+    /// Compile `code` to native machine code with the Cranelift backend and
+    /// run it. Only available when the `jit` feature is enabled.
+    #[cfg(feature = "jit")]
+    pub fn run_jit(&mut self, code: &[u8]) -> io::Result<()> {
+        // The native backend only implements the historical dialect: 8-bit
+        // cells, `left` at the origin panicking, and `,` at EOF zeroing the
+        // cell. Any other configuration runs on the interpreter so the two
+        // backends never disagree.
+        let Config {
+            cell_width: CellWidth::Eight,
+            edge: EdgeBehavior::Panic,
+            eof: EofPolicy::Zero,
+        } = self.tape.config
+        else {
+            return self.run(code);
+        };
+        let program = Program::compile(code, self.tape.config.cell_width)?;
+        jit::run(&program.ops, &mut self.input, &mut self.output)
     }
 
     pub fn output(&self) -> &O {
@@ -121,66 +510,229 @@ impl<I: Read, O: Write> Interpreter<I, O> {
     pub fn into_output(self) -> O {
         self.output
     }
+}
 
-    pub fn matching_for_left_paren(&self, current_index: usize, code: &[u8]) -> io::Result<usize> {
-        let mut encountered: usize = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
 
-        for (i, &character) in code[(current_index + 1)..].iter().enumerate() {
-            match character as char {
-                '[' => {
-                    encountered += 1;
-                }
-                ']' => {
-                    if encountered == 0 {
-                        return Ok(i);
-                    }
-                    encountered -= 1
-                }
-                _ => {}
-            }
-        }
+    fn ops(code: &str, width: CellWidth) -> Vec<Op> {
+        Program::compile(code.as_bytes(), width).unwrap().ops
+    }
 
-        Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid code"))
+    #[test]
+    fn folds_runs_and_drops_net_zero() {
+        assert_eq!(ops("+++", CellWidth::Eight), vec![Op::Add(3)]);
+        assert_eq!(ops("+++++-", CellWidth::Eight), vec![Op::Add(4)]);
+        assert_eq!(ops("+-", CellWidth::Eight), vec![]);
+        assert_eq!(ops(">><", CellWidth::Eight), vec![Op::Move(1)]);
+        assert_eq!(ops("<>", CellWidth::Eight), vec![]);
+        assert_eq!(ops("...", CellWidth::Eight), vec![Op::Output(3)]);
     }
 
-    pub fn matching_for_right_paren(&self, current_index: usize, code: &[u8]) -> io::Result<usize> {
-        let mut encountered: usize = 0;
+    #[test]
+    fn fold_respects_cell_width() {
+        // 256 `+` wraps to zero on 8-bit cells and is dropped entirely...
+        let code = "+".repeat(256);
+        assert_eq!(ops(&code, CellWidth::Eight), vec![]);
+        // ...but survives intact on wider cells.
+        assert_eq!(ops(&code, CellWidth::Sixteen), vec![Op::Add(256)]);
+        let code = "+".repeat(300);
+        assert_eq!(ops(&code, CellWidth::Sixteen), vec![Op::Add(300)]);
+    }
 
-        for (i, &character) in code[..current_index].iter().enumerate().rev() {
-            match character as char {
-                ']' => {
-                    encountered += 1;
-                }
-                '[' => {
-                    if encountered == 0 {
-                        return Ok(i);
-                    }
-                    encountered -= 1
-                }
-                _ => {}
+    #[test]
+    fn recognizes_clear_loops() {
+        assert_eq!(ops("[-]", CellWidth::Eight), vec![Op::SetZero]);
+        assert_eq!(ops("[+]", CellWidth::Eight), vec![Op::SetZero]);
+    }
+
+    #[test]
+    fn recognizes_copy_and_multiply_loops() {
+        assert_eq!(
+            ops("[->+<]", CellWidth::Eight),
+            vec![Op::MulAdd { offset: 1, factor: 1 }, Op::SetZero],
+        );
+        assert_eq!(
+            ops("[->++>+++<<]", CellWidth::Eight),
+            vec![
+                Op::MulAdd { offset: 1, factor: 2 },
+                Op::MulAdd { offset: 2, factor: 3 },
+                Op::SetZero,
+            ],
+        );
+    }
+
+    #[test]
+    fn rejects_non_simple_loops() {
+        // Net pointer move is non-zero, so it stays a real loop.
+        let compiled = ops("[>]", CellWidth::Eight);
+        assert!(matches!(
+            compiled.as_slice(),
+            [Op::JumpIfZero(_), Op::Move(1), Op::JumpIfNonZero(_)],
+        ));
+        // Head cell not decremented by exactly one.
+        assert!(try_simple_loop(b"-->+<", CellWidth::Eight).is_none());
+        // Pointer does not return to the head.
+        assert!(try_simple_loop(b"->+", CellWidth::Eight).is_none());
+        // A nested loop is never a simple idiom.
+        assert!(try_simple_loop(b"-[>]", CellWidth::Eight).is_none());
+    }
+
+    #[test]
+    fn unbalanced_brackets_are_invalid_data() {
+        let err = Program::compile(b"[", CellWidth::Eight).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = Program::compile(b"]", CellWidth::Eight).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = Program::compile(b"[[]", CellWidth::Eight).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
+
+/// Build a bracket jump table: `jumps[open] == close` and `jumps[close] ==
+/// open`. Unbalanced brackets are reported as `InvalidData` up front so
+/// malformed programs fail before producing any output.
+fn build_jumps(code: &[u8]) -> io::Result<Vec<usize>> {
+    let mut jumps = vec![0usize; code.len()];
+    let mut stack: Vec<usize> = Vec::new();
+
+    for (i, &character) in code.iter().enumerate() {
+        match character as char {
+            '[' => stack.push(i),
+            ']' => {
+                let open = stack
+                    .pop()
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Unmatched ]"))?;
+                jumps[open] = i;
+                jumps[i] = open;
             }
+            _ => {}
         }
+    }
 
-        Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid code"))
+    if !stack.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Unmatched ["));
     }
+
+    Ok(jumps)
 }
 
 fn main() -> io::Result<()> {
     let args = env::args().collect::<Vec<_>>();
 
-    if args.len() != 2 || args[1] == "-h" || args[1] == "--help" {
-        eprintln!("Usage: brainruck SOURCE_FILE");
-        process::exit(1);
+    const USAGE: &str = "Usage: brainruck [--jit] [--debug | --trace] \
+        [--cell-width 8|16|32] [--edge panic|wrap|grow] \
+        [--eof zero|ones|unchanged] SOURCE_FILE";
+
+    let mut jit = false;
+    let mut debug = false;
+    let mut trace = false;
+    let mut source: Option<&str> = None;
+    let mut config = Config::default();
+
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        // Flags that take a value pull the next argument; bail with usage if
+        // it is missing or unrecognized.
+        let mut value = || match rest.next() {
+            Some(value) => value.as_str(),
+            None => {
+                eprintln!("{USAGE}");
+                process::exit(1);
+            }
+        };
+        match arg.as_str() {
+            "-h" | "--help" => {
+                eprintln!("{USAGE}");
+                process::exit(1);
+            }
+            "--jit" => jit = true,
+            "--debug" => debug = true,
+            "--trace" => trace = true,
+            "--cell-width" => {
+                config.cell_width = match value() {
+                    "8" => CellWidth::Eight,
+                    "16" => CellWidth::Sixteen,
+                    "32" => CellWidth::ThirtyTwo,
+                    _ => {
+                        eprintln!("{USAGE}");
+                        process::exit(1);
+                    }
+                }
+            }
+            "--edge" => {
+                config.edge = match value() {
+                    "panic" => EdgeBehavior::Panic,
+                    "wrap" => EdgeBehavior::Wrap,
+                    "grow" => EdgeBehavior::Grow,
+                    _ => {
+                        eprintln!("{USAGE}");
+                        process::exit(1);
+                    }
+                }
+            }
+            "--eof" => {
+                config.eof = match value() {
+                    "zero" => EofPolicy::Zero,
+                    "ones" => EofPolicy::AllOnes,
+                    "unchanged" => EofPolicy::Unchanged,
+                    _ => {
+                        eprintln!("{USAGE}");
+                        process::exit(1);
+                    }
+                }
+            }
+            other => source = Some(other),
+        }
     }
 
+    let source = match source {
+        Some(source) => source,
+        None => {
+            eprintln!("{USAGE}");
+            process::exit(1);
+        }
+    };
+
     let mut code = String::new();
-    BufReader::new(File::open(&args[1])?).read_to_string(&mut code)?;
+    BufReader::new(File::open(source)?).read_to_string(&mut code)?;
+
+    // The debugger and tracer drive the interpreter a step at a time and take
+    // over stdin for their own command stream, so the program reads from an
+    // empty input (`,` observes EOF).
+    if debug || trace {
+        let output = BufWriter::new(io::stdout());
+        let mut interpreter = Interpreter::new(io::empty(), output, config);
+        if trace {
+            let mut ui = io::stderr();
+            debug::trace(&mut interpreter, code.as_bytes(), &mut ui)?;
+        } else {
+            let commands = io::stdin().lock();
+            let mut ui = io::stderr();
+            debug::repl(&mut interpreter, code.as_bytes(), commands, &mut ui)?;
+        }
+        return Ok(());
+    }
 
     let input = BufReader::new(io::stdin());
     let output = BufWriter::new(io::stdout());
 
-    let mut interpreter = Interpreter::new(input, output);
-    interpreter.run(code.as_bytes())?;
+    let mut interpreter = Interpreter::new(input, output, config);
+    if jit {
+        #[cfg(feature = "jit")]
+        {
+            interpreter.run_jit(code.as_bytes())?;
+        }
+        #[cfg(not(feature = "jit"))]
+        {
+            eprintln!("brainruck: built without the `jit` feature; interpreting instead");
+            interpreter.run(code.as_bytes())?;
+        }
+    } else {
+        interpreter.run(code.as_bytes())?;
+    }
 
     Ok(())
 }